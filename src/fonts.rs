@@ -0,0 +1,47 @@
+//! A shared, thread-local fontdb. usvg skips `<text>` conversion entirely whenever
+//! `Options.fontdb` is empty, and otherwise only rasterizes text it can resolve to a loaded face
+//! — so anywhere svg containing `<text>` gets parsed (`layout.svg`, generated title cards) needs
+//! one. `load_system_fonts` scans the filesystem, so it's cached per thread rather than repeated
+//! on every parse.
+
+use std::cell::RefCell;
+use usvg::fontdb;
+
+thread_local! {
+    static FONTDB: RefCell<(fontdb::Database, String)> = RefCell::new(load());
+}
+
+/// Loads every system font and picks one of their family names to pair with it: usvg's font
+/// resolver always tries `Options.font_family` as a last-resort exact match, so as long as this
+/// names a face that's actually in the db, text renders regardless of which generic/named fonts
+/// happen to be installed.
+fn load() -> (fontdb::Database, String) {
+    let mut db = fontdb::Database::new();
+    db.load_system_fonts();
+    let family = db
+        .faces()
+        .first()
+        .map(|face| face.family.clone())
+        .expect("no system fonts found to render svg text");
+    (db, family)
+}
+
+/// Builds `usvg::Options` with the cached fontdb and `font_family` set to a name guaranteed to
+/// resolve against it.
+pub fn options(keep_named_groups: bool) -> usvg::Options {
+    FONTDB.with(|f| {
+        let (db, family) = &*f.borrow();
+        usvg::Options {
+            keep_named_groups,
+            font_family: family.clone(),
+            fontdb: db.clone(),
+            ..usvg::Options::default()
+        }
+    })
+}
+
+/// The family name generated `font-family` attributes should reference, matching what
+/// `options()` sets `Options.font_family` to.
+pub fn family() -> String {
+    FONTDB.with(|f| f.borrow().1.clone())
+}
@@ -1,36 +1,48 @@
 //! Provides a Slick Video Clip to Identify CC-BY-SA Content
 
+mod fonts;
+mod output;
+mod preview;
+mod project;
+mod titlecard;
+
 use clap::{App, AppSettings::DeriveDisplayOrder, Arg};
 use rayon::prelude::*;
 use std::{
-    cell::RefCell,
+    collections::BTreeMap,
     io::{stdout, Write},
-    process::Command,
-    sync::atomic::{AtomicU32, Ordering},
+    process::{Command, Stdio},
+    sync::{
+        atomic::{AtomicU32, Ordering},
+        Mutex,
+    },
 };
+use output::{HwAccel, OutputProfile};
 use tiny_skia::*;
+use titlecard::license_card;
 use usvg::*;
 
 // build a standard rectangle version of animation, then fit to specified resolution and fps
 
-/// Loads an svg file and produces an svg tree
-fn load_svg(path: &str) -> Tree {
-    let data = std::fs::read(&path).unwrap();
-    let opt = Options::default();
-    Tree::from_data(&data, &opt).unwrap()
-}
-
 thread_local! {
-    static LAYOUT: RefCell<Tree> = RefCell::new(load_svg("layout.svg"));
+    static LAYOUT_SVG: Vec<u8> = std::fs::read("layout.svg").unwrap();
 }
 
-type LKRC<T> = std::thread::LocalKey<RefCell<T>>;
+/// Parses a fresh copy of `layout.svg`'s tree. `Tree`/`Node` cloning (via `Rc`) only bumps a
+/// refcount rather than copying nodes, so sharing one parsed `Tree` across frames would have
+/// every frame on a thread mutate the same group nodes as every other — `slide_in`'s `+=` would
+/// then accumulate across frames instead of starting from the layout's resting transform each
+/// time. Re-parsing from the cached bytes gives each frame its own independent node tree.
+fn fresh_layout() -> Tree {
+    LAYOUT_SVG.with(|data| parse_svg(data))
+}
 
-fn clone_rc<T>(x: &'static LKRC<T>) -> T
-where
-    T: Clone,
-{
-    x.with(|f| f.borrow().clone())
+fn parse_svg(data: &[u8]) -> Tree {
+    // layout.svg's "text" group wraps a real <text> element, and an empty Options.fontdb (the
+    // default) makes usvg skip <text> conversion outright, leaving the group childless — which
+    // `remove_empty_groups` then prunes regardless of `keep_named_groups`, turning every
+    // `node_by_id("text")` call below into a panic.
+    Tree::from_data(data, &fonts::options(false)).unwrap()
 }
 
 #[derive(Debug, Clone, Copy)]
@@ -41,35 +53,105 @@ impl Time {
         Self(self.0 - t0)
     }
 
-    fn until<F: FnOnce(Time) -> ()>(&self, dt: f64, f: F) -> Self {
+    fn until<F: FnOnce(Time)>(&self, dt: f64, f: F) -> Self {
         if 0.0 <= self.0 {
             f(Time(self.0.min(dt)));
         }
         *self
     }
 
-    fn during<F: FnOnce(Time) -> ()>(&self, t: f64, f: F) -> Self {
+    fn during<F: FnOnce(Time)>(&self, t: f64, f: F) -> Self {
         if 0.0 <= self.0 {
             f(*self);
         }
         self.wait(t)
     }
 
-    fn until_during<F: FnOnce(Time) -> ()>(&self, dt: f64, t: f64, f: F) -> Self {
+    fn until_during<F: FnOnce(Time)>(&self, dt: f64, t: f64, f: F) -> Self {
         self.during(t, |time| {
             time.until(dt, f);
         })
     }
 }
 
-fn slide_in(t: f64, n: &mut Node) {
-    // quadratic slide [0,1]
-    unimplemented!()
+/// An easing curve mapping normalized progress `t` in `[0, 1]` to eased progress, also in `[0, 1]`.
+#[derive(Debug, Clone, Copy)]
+enum Easing {
+    Linear,
+    QuadIn,
+    QuadOut,
+    QuadInOut,
+    CubicInOut,
+}
+
+impl Easing {
+    /// Evaluates the curve at `t`, clamping `t` to `[0, 1]` first.
+    fn apply(&self, t: f64) -> f64 {
+        let t = t.clamp(0.0, 1.0);
+        match self {
+            Self::Linear => t,
+            Self::QuadIn => t * t,
+            Self::QuadOut => t * (2.0 - t),
+            Self::QuadInOut => {
+                if t < 0.5 {
+                    2.0 * t * t
+                } else {
+                    -1.0 + (4.0 - 2.0 * t) * t
+                }
+            }
+            Self::CubicInOut => {
+                if t < 0.5 {
+                    4.0 * t * t * t
+                } else {
+                    let k = -2.0 * t + 2.0;
+                    1.0 - k * k * k / 2.0
+                }
+            }
+        }
+    }
+}
+
+impl std::str::FromStr for Easing {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "linear" => Ok(Self::Linear),
+            "quad-in" => Ok(Self::QuadIn),
+            "quad-out" => Ok(Self::QuadOut),
+            "quad-in-out" => Ok(Self::QuadInOut),
+            "cubic-in-out" => Ok(Self::CubicInOut),
+            _ => Err(format!("unrecognized easing '{}'", s)),
+        }
+    }
+}
+
+/// Sets `n`'s transform to slide in from `distance` units below its resting position, easing the
+/// offset down to zero as `t` goes 0→1.
+fn slide_in(ease: Easing, distance: f64, t: f64, n: &mut Node) {
+    let offset = distance * (1.0 - ease.apply(t));
+    if let NodeKind::Group(ref mut group) = *n.borrow_mut() {
+        // Offset from the group's existing resting transform rather than replacing it, so groups
+        // that aren't already at the origin in layout.svg still slide from their real position.
+        group.transform.f += offset;
+    }
+}
+
+/// Sets `n`'s opacity directly, clamping to a valid `Opacity`.
+fn set_opacity(n: &mut Node, opacity: f64) {
+    if let NodeKind::Group(ref mut group) = *n.borrow_mut() {
+        group.opacity = Opacity::new(opacity);
+    }
 }
 
-fn fade_in(t: f64, n: &mut Node) {
-    // quadratic fade [0,1]
-    unimplemented!()
+/// Sets `n`'s opacity to the eased progress of `t`, so it reveals over `[0, 1]`.
+fn fade_in(ease: Easing, t: f64, n: &mut Node) {
+    set_opacity(n, ease.apply(t));
+}
+
+/// Sets `n`'s opacity to the inverse eased progress of `t`, so it hides over `[0, 1]`.
+fn fade_out(ease: Easing, t: f64, n: &mut Node) {
+    set_opacity(n, 1.0 - ease.apply(t));
 }
 
 #[derive(Debug, Clone, Copy)]
@@ -87,7 +169,7 @@ impl From<(u32, u32)> for Resolution {
     }
 }
 
-/// The default resolution is 2160p
+// The default resolution is 2160p
 // maybe move this info the to clap app
 
 type Scene = dyn Fn(u32) -> Tree + Sync + Send;
@@ -109,6 +191,20 @@ enum FrameError {
     SavePng,
 }
 
+/// `Pixmap` stores premultiplied alpha, but ffmpeg's rawvideo `rgba` pix_fmt expects straight
+/// alpha — the same conversion `save_png` already does internally for the PNG path.
+fn unpremultiplied_rgba(pixmap: &Pixmap) -> Vec<u8> {
+    let mut out = Vec::with_capacity(pixmap.data().len());
+    for pixel in pixmap.pixels() {
+        let color = pixel.demultiply();
+        out.push(color.red());
+        out.push(color.green());
+        out.push(color.blue());
+        out.push(color.alpha());
+    }
+    out
+}
+
 impl<'a> Renderer<'a> {
     fn new(resolution: Resolution, framerate: f64, frame_length: u32, scene: &'a Scene) -> Self {
         Self {
@@ -119,7 +215,8 @@ impl<'a> Renderer<'a> {
         }
     }
 
-    fn render_frame(&self, frame_time: u32) -> Result<(), FrameError> {
+    /// Renders a single frame's svg tree to a pixmap, without writing it anywhere.
+    fn render_pixmap(&self, frame_time: u32) -> Result<Pixmap, FrameError> {
         let tree = (self.scene)(frame_time);
         let mut pixmap = Pixmap::new(self.resolution.width, self.resolution.height)
             .ok_or(FrameError::NewPixmap)?;
@@ -129,15 +226,24 @@ impl<'a> Renderer<'a> {
             pixmap.as_mut(),
         )
         .ok_or(FrameError::RenderSVG)?;
+        Ok(pixmap)
+    }
+
+    /// Renders a frame and saves it as a PNG, also handing back the rendered pixmap so callers
+    /// that sampled this frame for `--preview` don't need to re-render it.
+    fn render_frame(&self, frame_time: u32, frames_dir: &str) -> Result<Pixmap, FrameError> {
+        let pixmap = self.render_pixmap(frame_time)?;
         pixmap
-            .save_png(format!("frames/{:06}.png", frame_time + 1))
+            .save_png(format!("{}/{:06}.png", frames_dir, frame_time + 1))
             .map_err(|_| FrameError::SavePng)?;
-        Ok(())
+        Ok(pixmap)
     }
 
-    fn render(&self) -> Result<(), ()> {
+    /// Encodes already-rendered PNGs from `frames_dir` into a video, per `profile`.
+    fn render(&self, profile: &OutputProfile, frames_dir: &str) -> Result<(), ()> {
         Command::new("ffmpeg")
-            .args(&[
+            .args(profile.input_args())
+            .args([
                 // specify framerate
                 "-framerate",
                 &self.framerate.to_string(),
@@ -146,23 +252,208 @@ impl<'a> Renderer<'a> {
                 &format!("{}x{}", self.resolution.width, self.resolution.height),
                 // give location of rendered frames
                 "-i",
-                "frames/%06d.png",
+                &format!("{}/%06d.png", frames_dir),
                 // provide other options
                 "-y",
-                "-vcodec",
-                "libx264",
-                "-crf",
-                "15",
-                "-pix_fmt",
-                "yuv420p",
-                "video.mp4",
             ])
+            .args(profile.ffmpeg_args())
             .spawn()
             .map_err(|_| ())?
             .wait()
             .map_err(|_| ())?;
         Ok(())
     }
+
+    /// Renders every frame and pipes its raw rgba bytes directly into ffmpeg's stdin, skipping
+    /// the PNG-on-disk round trip. Rayon renders frames out of order, so completed frames are
+    /// buffered by index and only written to the pipe once every earlier frame has gone out.
+    /// Frames whose index is in `samples` are also stashed into `captured` for `--preview`.
+    fn render_stream(
+        &self,
+        profile: &OutputProfile,
+        samples: &std::collections::HashSet<u32>,
+        captured: &Mutex<BTreeMap<u32, Pixmap>>,
+    ) -> Result<(), ()> {
+        let mut child = Command::new("ffmpeg")
+            .args(profile.input_args())
+            .args([
+                "-f",
+                "rawvideo",
+                "-pix_fmt",
+                "rgba",
+                "-s",
+                &format!("{}x{}", self.resolution.width, self.resolution.height),
+                "-r",
+                &self.framerate.to_string(),
+                "-i",
+                "-",
+                "-y",
+            ])
+            .args(profile.ffmpeg_args())
+            .stdin(Stdio::piped())
+            .spawn()
+            .map_err(|_| ())?;
+        let stdin = Mutex::new(child.stdin.take().ok_or(())?);
+        let pending = Mutex::new((0u32, BTreeMap::<u32, Pixmap>::new()));
+
+        (0..self.frame_length)
+            .into_par_iter()
+            .try_for_each(|frame_time| -> Result<(), ()> {
+                let pixmap = self.render_pixmap(frame_time).map_err(|_| ())?;
+
+                // Bail out of just this frame on a poisoned lock rather than unwrapping, so one
+                // failed frame can't drag every other in-flight worker down with it.
+                let mut pending = pending.lock().map_err(|_| ())?;
+                pending.1.insert(frame_time, pixmap);
+                let mut stdin = stdin.lock().map_err(|_| ())?;
+                loop {
+                    let next = pending.0;
+                    let ready = match pending.1.remove(&next) {
+                        Some(ready) => ready,
+                        None => break,
+                    };
+                    if samples.contains(&next) {
+                        captured.lock().map_err(|_| ())?.insert(next, ready.clone());
+                    }
+                    stdin
+                        .write_all(&unpremultiplied_rgba(&ready))
+                        .map_err(|_| ())?;
+                    pending.0 += 1;
+                }
+                Ok(())
+            })?;
+
+        drop(stdin);
+        child.wait().map_err(|_| ())?;
+        Ok(())
+    }
+}
+
+/// Everything the scene closure needs to place the cc-by-sa animation and its title cards on the
+/// timeline. Grouped together so both the stateless CLI path and the resumable project-file path
+/// can build the same scene from their own parsed input.
+struct SceneParams {
+    resolution: Resolution,
+    framerate: f64,
+    delay: f64,
+    interval: f64,
+    entry: f64,
+    sustain: f64,
+    fade: f64,
+    leave: f64,
+    intro: f64,
+    outro: f64,
+    easing: Easing,
+    slide_distance: f64,
+    title: String,
+    author: String,
+    license_label: String,
+}
+
+impl SceneParams {
+    /// Total video length in seconds, including the intro/outro cards.
+    fn length(&self) -> f64 {
+        self.intro + self.delay + self.sustain + self.fade + self.leave + self.outro
+    }
+}
+
+/// Builds the per-frame `Scene` closure: title cards during the intro/outro, the cc-by-sa glyph
+/// animation in between.
+fn build_scene(p: SceneParams) -> impl Fn(u32) -> Tree + Sync + Send {
+    let SceneParams {
+        resolution,
+        framerate,
+        delay,
+        interval,
+        entry,
+        sustain,
+        fade,
+        leave,
+        intro,
+        outro,
+        easing,
+        slide_distance,
+        title,
+        author,
+        license_label,
+    } = p;
+    let length = intro + delay + sustain + fade + leave + outro;
+
+    let enter = move |layout: &mut Tree, id: &str, t: f64| {
+        let mut node = layout.node_by_id(id).unwrap();
+        fade_in(easing, t, &mut node);
+        slide_in(easing, slide_distance, t, &mut node);
+    };
+
+    // Fades a title card in over the first quarter of its `duration` and back out over the last.
+    let card_fade = move |time: f64, duration: f64, root: &mut Node| {
+        let fade_len = (duration * 0.25).clamp(f64::EPSILON, 1.0);
+        if time < fade_len {
+            fade_in(easing, time / fade_len, root);
+        } else if time > duration - fade_len {
+            fade_out(easing, (time - (duration - fade_len)) / fade_len, root);
+        }
+    };
+
+    move |frame_time: u32| {
+        let t = (frame_time as f64) / framerate;
+
+        if intro > 0.0 && t < intro {
+            let card = license_card(
+                resolution.width as f64,
+                resolution.height as f64,
+                &title,
+                &author,
+                &license_label,
+            );
+            let mut content = card.node_by_id(titlecard::CONTENT_GROUP_ID).unwrap();
+            card_fade(t, intro, &mut content);
+            return card;
+        }
+        let t = t - intro;
+
+        if outro > 0.0 && t >= length - intro - outro {
+            let card = license_card(
+                resolution.width as f64,
+                resolution.height as f64,
+                &title,
+                &author,
+                &license_label,
+            );
+            let mut content = card.node_by_id(titlecard::CONTENT_GROUP_ID).unwrap();
+            card_fade(t - (length - intro - outro), outro, &mut content);
+            return card;
+        }
+
+        let mut layout = fresh_layout();
+
+        Time(t)
+            .wait(delay)
+            .during(sustain, |time| {
+                time.until_during(entry, interval, |time| {
+                    enter(&mut layout, "cc", time.0 / entry);
+                })
+                .until_during(entry, interval, |time| {
+                    enter(&mut layout, "by", time.0 / entry);
+                })
+                .until_during(entry, interval, |time| {
+                    enter(&mut layout, "sa", time.0 / entry);
+                })
+                .until(entry, |time| {
+                    enter(&mut layout, "text", time.0 / entry);
+                });
+            })
+            .until_during(fade, fade, |time| {
+                // layout.root() is the svg node itself, which set_opacity can't touch; fade each
+                // of its top-level content groups instead so the screen actually dims to blank.
+                for mut child in layout.root().children() {
+                    fade_out(easing, time.0 / fade, &mut child);
+                }
+            })
+            .wait(leave);
+
+        layout
+    }
 }
 
 fn main() -> Result<(), ()> {
@@ -219,8 +510,114 @@ fn main() -> Result<(), ()> {
                 .help("Seconds of outro blank")
                 .default_value("0.5"),
         )
+        .arg(
+            Arg::with_name("easing")
+                .long("easing")
+                .help("Easing curve for symbol entry: linear, quad-in, quad-out, quad-in-out, cubic-in-out")
+                .default_value("quad-in-out"),
+        )
+        .arg(
+            Arg::with_name("slide-distance")
+                .long("slide-distance")
+                .help("Distance in svg units each symbol slides in from")
+                .default_value("40.0"),
+        )
+        .arg(
+            Arg::with_name("intro")
+                .long("intro")
+                .help("Seconds to show the intro title card (0 to disable)")
+                .default_value("0.0"),
+        )
+        .arg(
+            Arg::with_name("outro")
+                .long("outro")
+                .help("Seconds to show the outro title card (0 to disable)")
+                .default_value("0.0"),
+        )
+        .arg(
+            Arg::with_name("title")
+                .long("title")
+                .help("Title of the licensed work, shown on the intro/outro cards")
+                .default_value("This work"),
+        )
+        .arg(
+            Arg::with_name("author")
+                .long("author")
+                .help("Author of the licensed work, shown on the intro/outro cards")
+                .default_value("its author"),
+        )
+        .arg(
+            Arg::with_name("license-version")
+                .long("license-version")
+                .help("CC-BY-SA license version shown on the intro/outro cards")
+                .default_value("4.0"),
+        )
+        .arg(
+            Arg::with_name("codec")
+                .long("codec")
+                .help("Video codec: libx264, libx265, libsvtav1")
+                .default_value("libx264"),
+        )
+        .arg(
+            Arg::with_name("crf")
+                .long("crf")
+                .help("Constant rate factor (quality); lower is higher quality")
+                .default_value("15"),
+        )
+        .arg(
+            Arg::with_name("preset")
+                .long("preset")
+                .help("Encoder preset (e.g. an SVT-AV1 preset number)")
+                .takes_value(true),
+        )
+        .arg(
+            Arg::with_name("pixel-format")
+                .long("pixel-format")
+                .help("Output pixel format, e.g. yuv420p or yuv420p10le")
+                .default_value("yuv420p"),
+        )
+        .arg(
+            Arg::with_name("output")
+                .short("o")
+                .long("output")
+                .help("Output video path/container")
+                .default_value("video.mp4"),
+        )
+        .arg(
+            Arg::with_name("hwaccel")
+                .long("hwaccel")
+                .help("Hardware encoder to offload to: none, vaapi (requires the 'vaapi' feature)")
+                .default_value("none"),
+        )
+        .arg(
+            Arg::with_name("project")
+                .long("project")
+                .help("Path to a TOML project file; resumes a previous run via its [progress] table")
+                .takes_value(true),
+        )
+        .arg(
+            Arg::with_name("frames-dir")
+                .long("frames-dir")
+                .help("Write frames as PNGs to this directory instead of streaming them to ffmpeg")
+                .takes_value(true),
+        )
+        .arg(
+            Arg::with_name("preview")
+                .long("preview")
+                .help("Preview sampled frames in the terminal (kitty graphics protocol or sixel) after rendering"),
+        )
+        .arg(
+            Arg::with_name("preview-samples")
+                .long("preview-samples")
+                .help("Number of frames to sample across the timeline for --preview")
+                .default_value("5"),
+        )
         .get_matches();
 
+    if let Some(project_path) = matches.value_of("project") {
+        return project::run(std::path::Path::new(project_path));
+    }
+
     let resolution: Resolution = {
         let v: Vec<u32> = matches
             .value_of("resolution")
@@ -235,7 +632,7 @@ fn main() -> Result<(), ()> {
             .value_of(name)
             .unwrap()
             .parse()
-            .expect(&format!("non-float {}", name))
+            .unwrap_or_else(|_| panic!("non-float {}", name))
     };
     let framerate = match_f64("framerate");
     let delay = match_f64("delay");
@@ -245,56 +642,168 @@ fn main() -> Result<(), ()> {
     let fade = match_f64("fade");
     let leave = match_f64("leave");
     let length = delay + sustain + fade + leave;
+    let easing: Easing = matches
+        .value_of("easing")
+        .unwrap()
+        .parse()
+        .expect("invalid easing");
+    let slide_distance = match_f64("slide-distance");
+    let intro = match_f64("intro");
+    let outro = match_f64("outro");
+    let title = matches.value_of("title").unwrap().to_string();
+    let author = matches.value_of("author").unwrap().to_string();
+    let license_label = format!(
+        "CC-BY-SA {}",
+        matches.value_of("license-version").unwrap()
+    );
+    let length = intro + length + outro;
 
-    let scene = move |frame_time: u32| {
-        let mut layout = clone_rc(&LAYOUT);
+    let codec = matches
+        .value_of("codec")
+        .unwrap()
+        .parse()
+        .expect("invalid codec");
+    let crf: u32 = matches.value_of("crf").unwrap().parse().expect("non-integer crf");
+    let preset = matches.value_of("preset").map(String::from);
+    let pixel_format = matches.value_of("pixel-format").unwrap().to_string();
+    let output_path = matches.value_of("output").unwrap().to_string();
+    let hwaccel: HwAccel = matches
+        .value_of("hwaccel")
+        .unwrap()
+        .parse()
+        .expect("invalid hwaccel");
+    let profile = OutputProfile::new(codec, crf, preset, pixel_format, output_path, hwaccel)
+        .expect("incompatible output profile");
 
-        Time((frame_time as f64) / framerate)
-            .wait(delay)
-            .during(sustain, |time| {
-                time.until_during(entry, interval, |time| {
-                    // animate cc
-                    let mut cc = layout.node_by_id("cc").unwrap();
-                    fade_in(time.0 / entry, &mut cc);
-                    slide_in(time.0 / entry, &mut cc);
-                })
-                .until_during(entry, interval, |time| {
-                    // animate by
-                })
-                .until_during(entry, interval, |time| {
-                    // animate sa
-                })
-                .until(entry, |time| {
-                    // animate text
-                });
-            })
-            .until_during(fade, fade, |time| {
-                // animate fade
-            })
-            .wait(leave);
+    let scene = build_scene(SceneParams {
+        resolution,
+        framerate,
+        delay,
+        interval,
+        entry,
+        sustain,
+        fade,
+        leave,
+        intro,
+        outro,
+        easing,
+        slide_distance,
+        title,
+        author,
+        license_label,
+    });
+    let r = Renderer::new(resolution, framerate, (length * framerate) as u32, &scene);
 
-        layout
+    let samples: std::collections::HashSet<u32> = if matches.is_present("preview") {
+        let n: u32 = matches
+            .value_of("preview-samples")
+            .unwrap()
+            .parse()
+            .expect("non-integer preview-samples");
+        preview::sample_indices(r.frame_length, n).into_iter().collect()
+    } else {
+        std::collections::HashSet::new()
+    };
+    let captured = Mutex::new(BTreeMap::new());
+
+    let result = match matches.value_of("frames-dir") {
+        Some(frames_dir) => {
+            render_frames(
+                &r,
+                frames_dir,
+                &std::collections::HashSet::new(),
+                &samples,
+                &captured,
+            );
+            println!("Running ffmpeg to convert frames into video");
+            r.render(&profile, frames_dir)
+        }
+        None => {
+            println!("Rendering frames and streaming them directly into ffmpeg");
+            r.render_stream(&profile, &samples, &captured)
+        }
     };
-    let r = Renderer::new(resolution, framerate, (length * framerate) as u32, &scene);
 
-    let finished_frames = AtomicU32::new(0);
+    if matches.is_present("preview") {
+        preview::show_frames(&captured.into_inner().unwrap(), r.frame_length);
+    }
+
+    result
+}
+
+/// Renders every frame in `0..r.frame_length` not present in `skip` to PNGs in `frames_dir`, in
+/// parallel, printing progress as each one completes. Frames whose index is in `samples` are
+/// also stashed into `captured` for `--preview`, instead of being re-rendered afterwards.
+fn render_frames(
+    r: &Renderer,
+    frames_dir: &str,
+    skip: &std::collections::HashSet<u32>,
+    samples: &std::collections::HashSet<u32>,
+    captured: &Mutex<BTreeMap<u32, Pixmap>>,
+) {
+    let finished_frames = AtomicU32::new(skip.len() as u32);
     let make_progress = || {
         let i = finished_frames.fetch_add(1, Ordering::Relaxed);
         print!("\rRendering video frames ({}/{})", i + 1, r.frame_length);
         stdout().flush().unwrap();
     };
 
-    std::fs::create_dir_all("frames").expect("could not create frames directory");
+    std::fs::create_dir_all(frames_dir).expect("could not create frames directory");
     let _: Vec<()> = (0..r.frame_length)
         .into_par_iter()
+        .filter(|time| !skip.contains(time))
         .map(|time| {
-            let f = r.render_frame(time);
-            assert!(f.is_ok(), "failed to render frame {}", time);
+            let pixmap = r
+                .render_frame(time, frames_dir)
+                .unwrap_or_else(|_| panic!("failed to render frame {}", time));
+            if samples.contains(&time) {
+                captured.lock().unwrap().insert(time, pixmap);
+            }
             make_progress();
         })
         .collect();
     println!(); // finish progress
+}
 
-    println!("Running ffmpeg to convert frames into video");
-    r.render()
+/// Scans `frames_dir` for pngs already rendered for a video of `frame_length` frames, so a
+/// resumed run can skip re-rendering them.
+fn rendered_frame_indices(frame_length: u32, frames_dir: &str) -> std::collections::HashSet<u32> {
+    (0..frame_length)
+        .filter(|i| std::path::Path::new(&format!("{}/{:06}.png", frames_dir, i + 1)).exists())
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // `opacity` below keeps usvg's `ungroup_groups` pass from folding this group away during
+    // parsing (it only preserves groups that aren't otherwise redundant), so `node_by_id` below
+    // still finds it.
+    const FIXTURE_SVG: &str = r#"<svg xmlns="http://www.w3.org/2000/svg" width="100" height="100">
+        <g id="cc" transform="translate(10, 20)" opacity="0.99"><rect width="10" height="10"/></g>
+    </svg>"#;
+
+    fn transform_f(node: &Node) -> f64 {
+        match *node.borrow() {
+            NodeKind::Group(ref group) => group.transform.f,
+            _ => panic!("expected a group node"),
+        }
+    }
+
+    /// Regression test for a bug where every frame on a worker thread shared the same cached
+    /// `Tree`/`Node`s (cloning a `Tree` only bumps an `Rc` refcount), so `slide_in`'s `+=` kept
+    /// accumulating the slide offset onto the same nodes frame after frame instead of starting
+    /// from each frame's resting transform. Parsing a fresh tree per frame (what `fresh_layout`
+    /// now does) must leave every frame's starting transform untouched by the previous frame.
+    #[test]
+    fn slide_in_does_not_accumulate_across_frames() {
+        for _ in 0..5 {
+            let tree = parse_svg(FIXTURE_SVG.as_bytes());
+            let mut node = tree.node_by_id("cc").unwrap();
+            let resting = transform_f(&node);
+            slide_in(Easing::Linear, 40.0, 0.0, &mut node);
+            assert_eq!(transform_f(&node), resting + 40.0);
+        }
+    }
 }
@@ -0,0 +1,245 @@
+//! Encoder/container/quality configuration for the final `ffmpeg` encode.
+
+/// The video codecs `Renderer::render` knows how to drive.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Codec {
+    X264,
+    X265,
+    SvtAv1,
+}
+
+impl Codec {
+    /// The `-vcodec` value ffmpeg expects.
+    fn ffmpeg_name(&self) -> &'static str {
+        match self {
+            Self::X264 => "libx264",
+            Self::X265 => "libx265",
+            Self::SvtAv1 => "libsvtav1",
+        }
+    }
+
+    /// Whether this codec's ffmpeg encoder can take a 10-bit (or higher) pixel format.
+    fn supports_high_bit_depth(&self) -> bool {
+        match self {
+            Self::X264 => false,
+            Self::X265 | Self::SvtAv1 => true,
+        }
+    }
+
+    /// The vaapi hardware encoder for this codec, if ffmpeg ships one.
+    #[cfg(feature = "vaapi")]
+    fn vaapi_name(&self) -> Option<&'static str> {
+        match self {
+            Self::X264 => Some("h264_vaapi"),
+            Self::X265 => Some("hevc_vaapi"),
+            Self::SvtAv1 => None,
+        }
+    }
+}
+
+impl std::str::FromStr for Codec {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "libx264" => Ok(Self::X264),
+            "libx265" => Ok(Self::X265),
+            "libsvtav1" => Ok(Self::SvtAv1),
+            _ => Err(format!("unrecognized codec '{}'", s)),
+        }
+    }
+}
+
+/// Where frame encoding happens: in software, or offloaded to a GPU's video encode block.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum HwAccel {
+    None,
+    #[cfg(feature = "vaapi")]
+    Vaapi,
+}
+
+impl std::str::FromStr for HwAccel {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "none" => Ok(Self::None),
+            #[cfg(feature = "vaapi")]
+            "vaapi" => Ok(Self::Vaapi),
+            _ => Err(format!("unrecognized hwaccel '{}'", s)),
+        }
+    }
+}
+
+/// Whether an ffmpeg pixel format name encodes more than 8 bits per sample. Checked as an exact
+/// suffix match rather than `contains`, since e.g. `nv12` is an ordinary 8-bit format whose name
+/// just happens to contain "12" — it's the chroma subsampling layout, not a bit depth.
+fn is_high_bit_depth(pixel_format: &str) -> bool {
+    const HIGH_BIT_DEPTH_SUFFIXES: &[&str] = &[
+        "9le", "9be", "10le", "10be", "12le", "12be", "14le", "14be", "16le", "16be",
+    ];
+    HIGH_BIT_DEPTH_SUFFIXES
+        .iter()
+        .any(|suffix| pixel_format.ends_with(suffix))
+}
+
+/// An invalid combination of output settings, e.g. a high bit depth pixel format requested
+/// alongside a codec that can't encode it.
+#[derive(Debug)]
+pub struct ProfileError(String);
+
+impl std::fmt::Display for ProfileError {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        write!(f, "{}", self.0)
+    }
+}
+
+/// Everything `Renderer::render` needs to build its ffmpeg argument vector.
+#[derive(Debug, Clone)]
+pub struct OutputProfile {
+    pub codec: Codec,
+    pub crf: u32,
+    pub preset: Option<String>,
+    pub pixel_format: String,
+    pub output_path: String,
+    pub hwaccel: HwAccel,
+}
+
+impl OutputProfile {
+    pub fn new(
+        codec: Codec,
+        crf: u32,
+        preset: Option<String>,
+        pixel_format: String,
+        output_path: String,
+        hwaccel: HwAccel,
+    ) -> Result<Self, ProfileError> {
+        if is_high_bit_depth(&pixel_format) && !codec.supports_high_bit_depth() {
+            return Err(ProfileError(format!(
+                "pixel format '{}' requires a high bit depth encoder, but {} does not support one",
+                pixel_format,
+                codec.ffmpeg_name()
+            )));
+        }
+
+        #[cfg(feature = "vaapi")]
+        if hwaccel == HwAccel::Vaapi && codec.vaapi_name().is_none() {
+            return Err(ProfileError(format!(
+                "{} has no vaapi encoder; pass --hwaccel none or pick a different codec",
+                codec.ffmpeg_name()
+            )));
+        }
+
+        Ok(Self {
+            codec,
+            crf,
+            preset,
+            pixel_format,
+            output_path,
+            hwaccel,
+        })
+    }
+
+    /// Global ffmpeg options that must appear before `-i`, e.g. selecting the vaapi device to
+    /// upload frames to.
+    pub fn input_args(&self) -> Vec<String> {
+        match self.hwaccel {
+            #[cfg(feature = "vaapi")]
+            HwAccel::Vaapi => vec![
+                "-vaapi_device".to_string(),
+                "/dev/dri/renderD128".to_string(),
+            ],
+            HwAccel::None => Vec::new(),
+        }
+    }
+
+    /// Builds the trailing ffmpeg args this profile controls (codec, quality, pixel format,
+    /// preset, and output path) — everything after the input-side `-framerate`/`-s`/`-i` args.
+    pub fn ffmpeg_args(&self) -> Vec<String> {
+        match self.hwaccel {
+            #[cfg(feature = "vaapi")]
+            HwAccel::Vaapi => self.vaapi_ffmpeg_args(),
+            HwAccel::None => self.software_ffmpeg_args(),
+        }
+    }
+
+    fn software_ffmpeg_args(&self) -> Vec<String> {
+        let mut args = vec![
+            "-vcodec".to_string(),
+            self.codec.ffmpeg_name().to_string(),
+            "-crf".to_string(),
+            self.crf.to_string(),
+            "-pix_fmt".to_string(),
+            self.pixel_format.clone(),
+        ];
+        if let Some(preset) = &self.preset {
+            args.push("-preset".to_string());
+            args.push(preset.clone());
+        }
+        args.push(self.output_path.clone());
+        args
+    }
+
+    /// Uploads decoded frames to the GPU and encodes with the codec's vaapi counterpart instead
+    /// of its software encoder. Vaapi encoders take `-qp` rather than `-crf`. `OutputProfile::new`
+    /// already rejected any codec with no vaapi encoder, so this always succeeds.
+    #[cfg(feature = "vaapi")]
+    fn vaapi_ffmpeg_args(&self) -> Vec<String> {
+        let vaapi_codec = self
+            .codec
+            .vaapi_name()
+            .expect("codec has no vaapi encoder");
+        let mut args = vec![
+            "-vf".to_string(),
+            "format=nv12,hwupload".to_string(),
+            "-vcodec".to_string(),
+            vaapi_codec.to_string(),
+            "-qp".to_string(),
+            self.crf.to_string(),
+        ];
+        if let Some(preset) = &self.preset {
+            args.push("-preset".to_string());
+            args.push(preset.clone());
+        }
+        args.push(self.output_path.clone());
+        args
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn profile(pixel_format: &str, codec: Codec) -> Result<OutputProfile, ProfileError> {
+        OutputProfile::new(
+            codec,
+            20,
+            None,
+            pixel_format.to_string(),
+            "out.mp4".to_string(),
+            HwAccel::None,
+        )
+    }
+
+    /// Regression test: `nv12` is an 8-bit format (the "12" names its chroma subsampling layout,
+    /// not a bit depth), so libx264 must be able to take it.
+    #[test]
+    fn nv12_is_accepted_by_x264() {
+        assert!(profile("nv12", Codec::X264).is_ok());
+    }
+
+    #[test]
+    fn yuv420p_is_accepted_by_x264() {
+        assert!(profile("yuv420p", Codec::X264).is_ok());
+    }
+
+    #[test]
+    fn yuv420p10le_is_rejected_by_x264() {
+        assert!(profile("yuv420p10le", Codec::X264).is_err());
+    }
+
+    #[test]
+    fn yuv420p10le_is_accepted_by_x265() {
+        assert!(profile("yuv420p10le", Codec::X265).is_ok());
+    }
+}
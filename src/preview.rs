@@ -0,0 +1,205 @@
+//! In-terminal preview of rendered frames, via the kitty graphics protocol or sixel, so the
+//! animation timing can be sanity-checked over ssh without pulling down the encoded mp4.
+
+use std::collections::{BTreeMap, HashMap};
+use std::io::{stdout, Write};
+use tiny_skia::Pixmap;
+
+/// Target size, in pixels, a sampled frame is downscaled to before being emitted.
+const PREVIEW_WIDTH: u32 = 480;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum TerminalGraphics {
+    Kitty,
+    Sixel,
+    Unsupported,
+}
+
+/// Guesses what the current terminal can display, from environment variables alone.
+fn detect() -> TerminalGraphics {
+    let term = std::env::var("TERM").unwrap_or_default();
+    let term_program = std::env::var("TERM_PROGRAM").unwrap_or_default();
+
+    if std::env::var("KITTY_WINDOW_ID").is_ok() || term_program == "kitty" || term.contains("kitty")
+    {
+        TerminalGraphics::Kitty
+    } else if term.contains("sixel") || term.contains("mlterm") || term.contains("foot") {
+        TerminalGraphics::Sixel
+    } else {
+        TerminalGraphics::Unsupported
+    }
+}
+
+/// Picks `samples` frame indices evenly spaced across `0..frame_length`, for a caller to stash
+/// pixmaps for as it renders them.
+pub fn sample_indices(frame_length: u32, samples: u32) -> Vec<u32> {
+    let samples = samples.max(1).min(frame_length.max(1));
+    (0..samples)
+        .map(|i| i * frame_length.saturating_sub(1) / samples)
+        .collect()
+}
+
+/// Previews frames that were already rendered (and stashed by index via `sample_indices`)
+/// elsewhere, so `--preview` doesn't pay for a second rasterization pass.
+pub fn show_frames(frames: &BTreeMap<u32, Pixmap>, frame_length: u32) {
+    let graphics = detect();
+    if graphics == TerminalGraphics::Unsupported {
+        eprintln!(
+            "--preview: $TERM ('{}') doesn't look kitty- or sixel-capable; skipping preview",
+            std::env::var("TERM").unwrap_or_default()
+        );
+        return;
+    }
+
+    for (&frame_time, pixmap) in frames {
+        println!("frame {}/{}:", frame_time + 1, frame_length);
+        match graphics {
+            TerminalGraphics::Kitty => print_kitty(pixmap),
+            TerminalGraphics::Sixel => print_sixel(pixmap),
+            TerminalGraphics::Unsupported => unreachable!(),
+        }
+    }
+}
+
+/// Nearest-neighbor downscale of `pixmap`'s rgba buffer to `dst_w`x`dst_h`.
+fn downscale(pixmap: &Pixmap, dst_w: u32, dst_h: u32) -> Vec<u8> {
+    let src_w = pixmap.width();
+    let src_h = pixmap.height();
+    let data = pixmap.data();
+    let mut out = vec![0u8; (dst_w * dst_h * 4) as usize];
+    for y in 0..dst_h {
+        let sy = y * src_h / dst_h;
+        for x in 0..dst_w {
+            let sx = x * src_w / dst_w;
+            let src_i = ((sy * src_w + sx) * 4) as usize;
+            let dst_i = ((y * dst_w + x) * 4) as usize;
+            out[dst_i..dst_i + 4].copy_from_slice(&data[src_i..src_i + 4]);
+        }
+    }
+    out
+}
+
+fn preview_size(pixmap: &Pixmap) -> (u32, u32) {
+    let aspect = pixmap.height() as f64 / pixmap.width() as f64;
+    (PREVIEW_WIDTH, (PREVIEW_WIDTH as f64 * aspect).round() as u32)
+}
+
+/// Emits a downscaled frame as a kitty graphics protocol escape sequence, chunking the base64
+/// payload to the protocol's 4096-byte-per-chunk limit.
+fn print_kitty(pixmap: &Pixmap) {
+    let (w, h) = preview_size(pixmap);
+    let rgba = downscale(pixmap, w, h);
+    let encoded = base64_encode(&rgba);
+
+    let mut chunks = encoded.as_bytes().chunks(4096).peekable();
+    let mut first = true;
+    while let Some(chunk) = chunks.next() {
+        let more = if chunks.peek().is_some() { 1 } else { 0 };
+        let payload = std::str::from_utf8(chunk).unwrap();
+        if first {
+            print!("\x1b_Ga=T,f=32,s={},v={},m={};{}\x1b\\", w, h, more, payload);
+            first = false;
+        } else {
+            print!("\x1b_Gm={};{}\x1b\\", more, payload);
+        }
+    }
+    println!();
+    stdout().flush().unwrap();
+}
+
+/// Emits a downscaled frame as sixel data, quantized onto a 6x6x6 color cube so each pixel fits
+/// one of a small set of palette registers.
+fn print_sixel(pixmap: &Pixmap) {
+    let (w, h) = preview_size(pixmap);
+    let rgba = downscale(pixmap, w, h);
+
+    let quantize = |c: u8| (c as u16 * 5 / 255) as u8;
+
+    let mut palette: HashMap<(u8, u8, u8), u32> = HashMap::new();
+    let mut next_register = 0u32;
+    let mut register_defs = String::new();
+    let mut body = String::new();
+
+    let bands = h.div_ceil(6);
+    for band in 0..bands {
+        let y0 = band * 6;
+        let rows_in_band = (h - y0).min(6);
+
+        let mut masks_by_color: HashMap<(u8, u8, u8), Vec<u8>> = HashMap::new();
+        for x in 0..w {
+            for row in 0..rows_in_band {
+                let y = y0 + row;
+                let i = ((y * w + x) * 4) as usize;
+                let color = (quantize(rgba[i]), quantize(rgba[i + 1]), quantize(rgba[i + 2]));
+                let mask = masks_by_color
+                    .entry(color)
+                    .or_insert_with(|| vec![0u8; w as usize]);
+                mask[x as usize] |= 1 << row;
+            }
+        }
+
+        for (color, mask) in &masks_by_color {
+            let register = *palette.entry(*color).or_insert_with(|| {
+                let register = next_register;
+                register_defs.push_str(&format!(
+                    "#{};2;{};{};{}",
+                    register,
+                    color.0 as u32 * 100 / 5,
+                    color.1 as u32 * 100 / 5,
+                    color.2 as u32 * 100 / 5
+                ));
+                next_register += 1;
+                register
+            });
+
+            body.push_str(&format!("#{}", register));
+            let mut x = 0usize;
+            while x < mask.len() {
+                let value = mask[x];
+                let mut run = 1;
+                while x + run < mask.len() && mask[x + run] == value {
+                    run += 1;
+                }
+                let ch = (63 + value) as char;
+                if run > 3 {
+                    body.push_str(&format!("!{}{}", run, ch));
+                } else {
+                    for _ in 0..run {
+                        body.push(ch);
+                    }
+                }
+                x += run;
+            }
+            body.push('$');
+        }
+        body.push('-');
+    }
+
+    print!("\x1bPq{}{}\x1b\\", register_defs, body);
+    println!();
+    stdout().flush().unwrap();
+}
+
+fn base64_encode(data: &[u8]) -> String {
+    const ALPHABET: &[u8; 64] =
+        b"ABCDEFGHIJKLMNOPQRSTUVWXYZabcdefghijklmnopqrstuvwxyz0123456789+/";
+    let mut out = String::with_capacity(data.len().div_ceil(3) * 4);
+    for chunk in data.chunks(3) {
+        let b0 = chunk[0];
+        let b1 = *chunk.get(1).unwrap_or(&0);
+        let b2 = *chunk.get(2).unwrap_or(&0);
+        out.push(ALPHABET[(b0 >> 2) as usize] as char);
+        out.push(ALPHABET[(((b0 & 0x03) << 4) | (b1 >> 4)) as usize] as char);
+        out.push(if chunk.len() > 1 {
+            ALPHABET[(((b1 & 0x0f) << 2) | (b2 >> 6)) as usize] as char
+        } else {
+            '='
+        });
+        out.push(if chunk.len() > 2 {
+            ALPHABET[(b2 & 0x3f) as usize] as char
+        } else {
+            '='
+        });
+    }
+    out
+}
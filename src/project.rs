@@ -0,0 +1,201 @@
+//! Resumable rendering driven by a TOML project file.
+//!
+//! Unlike the stateless CLI path in `main`, a project file records its own progress: once frames
+//! are rendered or the video is encoded, re-running against the same file skips that phase.
+
+use crate::output::{Codec, HwAccel, OutputProfile};
+use crate::{
+    build_scene, rendered_frame_indices, render_frames, Easing, Renderer, Resolution, SceneParams,
+};
+use serde::{Deserialize, Serialize};
+use std::path::Path;
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct Project {
+    pub resolution: (u32, u32),
+    pub framerate: f64,
+    pub timing: Timing,
+    #[serde(default)]
+    pub text: TextSettings,
+    pub output: OutputSettings,
+    #[serde(default)]
+    pub progress: Progress,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct Timing {
+    pub delay: f64,
+    pub interval: f64,
+    pub entry: f64,
+    pub sustain: f64,
+    pub fade: f64,
+    pub leave: f64,
+    #[serde(default)]
+    pub intro: f64,
+    #[serde(default)]
+    pub outro: f64,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct TextSettings {
+    #[serde(default = "default_easing")]
+    pub easing: String,
+    #[serde(default = "default_slide_distance")]
+    pub slide_distance: f64,
+    #[serde(default = "default_title")]
+    pub title: String,
+    #[serde(default = "default_author")]
+    pub author: String,
+    #[serde(default = "default_license_version")]
+    pub license_version: String,
+}
+
+impl Default for TextSettings {
+    fn default() -> Self {
+        Self {
+            easing: default_easing(),
+            slide_distance: default_slide_distance(),
+            title: default_title(),
+            author: default_author(),
+            license_version: default_license_version(),
+        }
+    }
+}
+
+fn default_easing() -> String {
+    "quad-in-out".to_string()
+}
+fn default_slide_distance() -> f64 {
+    40.0
+}
+fn default_title() -> String {
+    "This work".to_string()
+}
+fn default_author() -> String {
+    "its author".to_string()
+}
+fn default_license_version() -> String {
+    "4.0".to_string()
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct OutputSettings {
+    pub codec: String,
+    pub crf: u32,
+    #[serde(default)]
+    pub preset: Option<String>,
+    #[serde(default = "default_pixel_format")]
+    pub pixel_format: String,
+    pub output_path: String,
+    #[serde(default)]
+    pub hwaccel: String,
+}
+
+fn default_pixel_format() -> String {
+    "yuv420p".to_string()
+}
+
+impl OutputSettings {
+    fn to_profile(&self) -> OutputProfile {
+        let codec: Codec = self.codec.parse().expect("invalid codec in project file");
+        let hwaccel: HwAccel = if self.hwaccel.is_empty() {
+            HwAccel::None
+        } else {
+            self.hwaccel.parse().expect("invalid hwaccel in project file")
+        };
+        OutputProfile::new(
+            codec,
+            self.crf,
+            self.preset.clone(),
+            self.pixel_format.clone(),
+            self.output_path.clone(),
+            hwaccel,
+        )
+        .expect("incompatible output profile in project file")
+    }
+}
+
+/// Tracks which expensive phases of a render have already completed, so re-running against the
+/// same project file resumes instead of starting over.
+#[derive(Debug, Default, Serialize, Deserialize)]
+pub struct Progress {
+    #[serde(default)]
+    pub frames_rendered: bool,
+    #[serde(default)]
+    pub video_encoded: bool,
+}
+
+impl Project {
+    fn load(path: &Path) -> Self {
+        let data = std::fs::read_to_string(path).expect("could not read project file");
+        toml::from_str(&data).expect("invalid project file")
+    }
+
+    fn save(&self, path: &Path) {
+        let data = toml::to_string_pretty(self).expect("could not serialize project");
+        std::fs::write(path, data).expect("could not write project file");
+    }
+
+    fn scene_params(&self) -> SceneParams {
+        let easing: Easing = self
+            .text
+            .easing
+            .parse()
+            .expect("invalid easing in project file");
+        SceneParams {
+            resolution: Resolution::from(self.resolution),
+            framerate: self.framerate,
+            delay: self.timing.delay,
+            interval: self.timing.interval,
+            entry: self.timing.entry,
+            sustain: self.timing.sustain,
+            fade: self.timing.fade,
+            leave: self.timing.leave,
+            intro: self.timing.intro,
+            outro: self.timing.outro,
+            easing,
+            slide_distance: self.text.slide_distance,
+            title: self.text.title.clone(),
+            author: self.text.author.clone(),
+            license_label: format!("CC-BY-SA {}", self.text.license_version),
+        }
+    }
+}
+
+/// Runs (or resumes) a render driven by the project file at `path`, writing progress back after
+/// each completed phase.
+pub fn run(path: &Path) -> Result<(), ()> {
+    let mut project = Project::load(path);
+
+    let params = project.scene_params();
+    let resolution = params.resolution;
+    let framerate = params.framerate;
+    let frame_length = (params.length() * framerate) as u32;
+    let scene = build_scene(params);
+    let r = Renderer::new(resolution, framerate, frame_length, &scene);
+
+    let frames_dir = "frames";
+
+    if !project.progress.frames_rendered {
+        let already = rendered_frame_indices(r.frame_length, frames_dir);
+        render_frames(
+            &r,
+            frames_dir,
+            &already,
+            &std::collections::HashSet::new(),
+            &std::sync::Mutex::new(std::collections::BTreeMap::new()),
+        );
+        project.progress.frames_rendered = true;
+        project.save(path);
+    }
+
+    if !project.progress.video_encoded {
+        println!("Running ffmpeg to convert frames into video");
+        let profile = project.output.to_profile();
+        r.render(&profile, frames_dir).expect("ffmpeg failed");
+        project.progress.video_encoded = true;
+        project.save(path);
+    }
+
+    Ok(())
+}
@@ -0,0 +1,130 @@
+//! Programmatically builds svg title cards, since intro/outro text isn't part of `layout.svg`.
+//!
+//! Cards are assembled as svg markup and parsed with `Tree::from_str`, rather than built
+//! node-by-node: usvg converts `<text>` into paths as part of parsing, so there's no public
+//! `NodeKind::Text` to construct directly.
+
+use crate::fonts;
+use usvg::*;
+
+/// id of the group a built card's background and text live under, so callers can look it up to
+/// fade the card in/out.
+pub const CONTENT_GROUP_ID: &str = "content";
+
+/// Builds a title card: a solid background behind one or more lines of centered text.
+pub struct TitleCard {
+    width: f64,
+    height: f64,
+    background: &'static str,
+    text_color: &'static str,
+    font_size: f64,
+    lines: Vec<String>,
+}
+
+impl TitleCard {
+    pub fn new(width: f64, height: f64) -> Self {
+        Self {
+            width,
+            height,
+            background: "black",
+            text_color: "white",
+            font_size: height / 24.0,
+            lines: Vec::new(),
+        }
+    }
+
+    /// Adds a line of text, stacked below any previously added lines.
+    pub fn line(mut self, text: impl Into<String>) -> Self {
+        self.lines.push(text.into());
+        self
+    }
+
+    /// Renders the accumulated lines into a standalone svg tree sized to `width`x`height`.
+    pub fn build(self) -> Tree {
+        let font_family = fonts::family();
+        let line_height = self.font_size * 1.4;
+        let start_y = self.height / 2.0 - (self.lines.len() as f64 - 1.0) * line_height / 2.0;
+
+        let mut text_elements = String::new();
+        for (i, line) in self.lines.iter().enumerate() {
+            let y = start_y + i as f64 * line_height;
+            text_elements.push_str(&format!(
+                "<text x=\"{x}\" y=\"{y}\" font-size=\"{size}\" font-family=\"{font}\" fill=\"{color}\" text-anchor=\"middle\">{text}</text>",
+                x = self.width / 2.0,
+                y = y,
+                size = self.font_size,
+                font = escape_xml(&font_family),
+                color = self.text_color,
+                text = escape_xml(line),
+            ));
+        }
+
+        let svg = format!(
+            r#"<svg xmlns="http://www.w3.org/2000/svg" width="{w}" height="{h}" viewBox="0 0 {w} {h}">
+                <g id="{id}">
+                    <rect x="0" y="0" width="{w}" height="{h}" fill="{bg}"/>
+                    {text_elements}
+                </g>
+            </svg>"#,
+            w = self.width,
+            h = self.height,
+            id = CONTENT_GROUP_ID,
+            bg = self.background,
+            text_elements = text_elements,
+        );
+
+        // Without `keep_named_groups`, usvg's `ungroup_groups` pass drops the `content` group
+        // regardless of its id, since nothing else in the document references it — leaving
+        // callers' `node_by_id(CONTENT_GROUP_ID)` to panic.
+        Tree::from_str(&svg, &fonts::options(true)).expect("generated title card svg is invalid")
+    }
+}
+
+fn escape_xml(text: &str) -> String {
+    text.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+}
+
+/// Builds the "this work is licensed" card shown before/after the main cc-by-sa animation.
+pub fn license_card(width: f64, height: f64, title: &str, author: &str, license_label: &str) -> Tree {
+    TitleCard::new(width, height)
+        .line(title.to_string())
+        .line(format!("by {}", author))
+        .line(format!("is licensed under {}", license_label))
+        .build()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Regression test: without `keep_named_groups`, usvg's `ungroup_groups` pass drops the
+    /// `content` group during parsing since nothing else in the document references its id,
+    /// leaving callers' `node_by_id(CONTENT_GROUP_ID)` to panic on every intro/outro frame.
+    #[test]
+    fn content_group_survives_parsing() {
+        let card = TitleCard::new(1920.0, 1080.0).line("hello").build();
+        assert!(card.node_by_id(CONTENT_GROUP_ID).is_some());
+    }
+
+    /// Regression test: an empty `Options.fontdb` (the default) makes usvg skip `<text>`
+    /// conversion altogether, so the group surviving parsing isn't enough — it needs to actually
+    /// contain the glyph paths usvg converts text into.
+    #[test]
+    fn text_renders_as_paths() {
+        let card = TitleCard::new(1920.0, 1080.0).line("hello").build();
+        let content = card.node_by_id(CONTENT_GROUP_ID).unwrap();
+        let path_count = content
+            .descendants()
+            .filter(|n| matches!(*n.borrow(), NodeKind::Path(_)))
+            .count();
+        // One path is the background rect; an empty fontdb would leave it at exactly 1 with the
+        // "hello" text silently skipped, so more than that means glyphs actually got converted.
+        assert!(
+            path_count > 1,
+            "expected glyph paths under the content group in addition to the background rect, found {}",
+            path_count
+        );
+    }
+}